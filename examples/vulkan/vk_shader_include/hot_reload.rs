@@ -0,0 +1,333 @@
+//! Opt-in runtime shader loading for this example.
+//!
+//! The `vs`/`fs` modules in `mod.rs` embed GLSL at compile time via `vulkano_shaders::shader!`,
+//! so tweaking the fragment shader means a full rebuild. This module instead reads the GLSL
+//! source (and its `#include` dependencies, e.g. `lfos.glsl`) from disk, compiles it with
+//! `shaderc` at startup, and polls the files' modification times each frame so edits take
+//! effect without restarting the example. On a compile error the last good pipeline is kept
+//! alive and the error is printed instead of crashing, so a typo mid-edit doesn't kill the
+//! sketch.
+//!
+//! This assumes the recompiled shader keeps the same vertex layout and push-constant interface
+//! as the pipeline it replaces — changing those still requires a restart.
+
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use nannou::vulkano::descriptor::descriptor::{DescriptorDesc, ShaderStages};
+use nannou::vulkano::descriptor::pipeline_layout::{PipelineLayoutDesc, PipelineLayoutDescPcRange};
+use nannou::vulkano::device::Device;
+use nannou::vulkano::format::Format;
+use nannou::vulkano::framebuffer::{RenderPassAbstract, Subpass};
+use nannou::vulkano::pipeline::shader::{
+    GraphicsShaderType, ShaderInterfaceDef, ShaderInterfaceDefEntry, ShaderModule,
+};
+use nannou::vulkano::pipeline::{GraphicsPipeline, GraphicsPipelineAbstract};
+
+use crate::Vertex;
+
+/// Entry point name every compiled stage uses (matches the `shaderc` `entry_point_name` passed
+/// to `compile_into_spirv` below).
+const ENTRY_POINT: &[u8] = b"main\0";
+
+/// A shader stage loaded from disk, tracking its own and its includes' modification times so we
+/// know when to recompile.
+struct WatchedSource {
+    entry_path: PathBuf,
+    include_dirs: Vec<PathBuf>,
+    last_modified: SystemTime,
+}
+
+impl WatchedSource {
+    fn new(entry_path: PathBuf, include_dirs: Vec<PathBuf>) -> Self {
+        let last_modified = modified_time(&entry_path);
+        WatchedSource {
+            entry_path,
+            include_dirs,
+            last_modified,
+        }
+    }
+
+    /// Returns `true` if the entry file or any of its include directories have changed since the
+    /// last check, updating the tracked timestamp as a side effect.
+    fn poll_changed(&mut self) -> bool {
+        let latest = std::iter::once(modified_time(&self.entry_path))
+            .chain(self.include_dirs.iter().flat_map(|dir| {
+                std::fs::read_dir(dir)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Result::ok)
+                    .map(|entry| modified_time(&entry.path()))
+            }))
+            .max()
+            .unwrap_or(self.last_modified);
+
+        if latest > self.last_modified {
+            self.last_modified = latest;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> SystemTime {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Compiles GLSL to SPIR-V bytes, resolving `#include <...>` against `include_dirs` in priority
+/// order.
+fn compile(
+    kind: shaderc::ShaderKind,
+    entry_path: &Path,
+    include_dirs: &[PathBuf],
+) -> Result<Vec<u8>, String> {
+    let source = std::fs::read_to_string(entry_path)
+        .map_err(|err| format!("failed to read {}: {}", entry_path.display(), err))?;
+
+    let mut compiler = shaderc::Compiler::new().ok_or("failed to initialise shaderc")?;
+    let mut options = shaderc::CompileOptions::new().ok_or("failed to initialise shaderc options")?;
+    let dirs = include_dirs.to_vec();
+    options.set_include_callback(move |name, _kind, _source, _depth| {
+        dirs.iter()
+            .map(|dir| dir.join(name))
+            .find(|path| path.is_file())
+            .and_then(|path| std::fs::read_to_string(&path).ok().map(|content| (path, content)))
+            .map(|(path, content)| shaderc::ResolvedInclude {
+                resolved_name: path.display().to_string(),
+                content,
+            })
+            .ok_or_else(|| format!("include not found: {}", name))
+    });
+
+    let binary = compiler
+        .compile_into_spirv(
+            &source,
+            kind,
+            &entry_path.display().to_string(),
+            "main",
+            Some(&options),
+        )
+        .map_err(|err| err.to_string())?;
+
+    Ok(binary.as_binary_u8().to_vec())
+}
+
+/// Owns the live `GraphicsPipeline` along with the watched sources used to rebuild it.
+pub struct ReloadingPipeline {
+    vertex: WatchedSource,
+    fragment: WatchedSource,
+    device: Arc<Device>,
+    render_pass: Arc<RenderPassAbstract + Send + Sync>,
+    pipeline: Arc<GraphicsPipelineAbstract + Send + Sync>,
+}
+
+impl ReloadingPipeline {
+    /// Compile the given GLSL sources from disk and start watching them for changes. If that
+    /// first compilation fails, the error is printed and `seed_pipeline` (the pipeline built from
+    /// the compile-time `vs`/`fs` modules in `mod.rs`) is served until a later successful
+    /// recompilation replaces it.
+    pub fn new(
+        device: Arc<Device>,
+        render_pass: Arc<RenderPassAbstract + Send + Sync>,
+        vertex_path: PathBuf,
+        fragment_path: PathBuf,
+        fragment_include_dirs: Vec<PathBuf>,
+        seed_pipeline: Arc<GraphicsPipelineAbstract + Send + Sync>,
+    ) -> Self {
+        let vertex = WatchedSource::new(vertex_path, Vec::new());
+        let fragment = WatchedSource::new(fragment_path, fragment_include_dirs);
+        let pipeline = match build_pipeline(&device, &render_pass, &vertex, &fragment) {
+            Ok(pipeline) => pipeline,
+            Err(err) => {
+                eprintln!("shader compile at startup failed, using built-in pipeline:\n{}", err);
+                seed_pipeline
+            }
+        };
+        ReloadingPipeline {
+            vertex,
+            fragment,
+            device,
+            render_pass,
+            pipeline,
+        }
+    }
+
+    /// The currently active pipeline.
+    pub fn pipeline(&self) -> Arc<GraphicsPipelineAbstract + Send + Sync> {
+        self.pipeline.clone()
+    }
+
+    /// Check whether either shader stage has changed on disk and, if so, attempt to recompile
+    /// and rebuild the pipeline. Errors are printed and the previous pipeline is kept.
+    pub fn poll(&mut self) {
+        let vertex_changed = self.vertex.poll_changed();
+        let fragment_changed = self.fragment.poll_changed();
+        if !vertex_changed && !fragment_changed {
+            return;
+        }
+
+        match build_pipeline(&self.device, &self.render_pass, &self.vertex, &self.fragment) {
+            Ok(pipeline) => self.pipeline = pipeline,
+            Err(err) => eprintln!("shader hot-reload failed, keeping previous pipeline:\n{}", err),
+        }
+    }
+}
+
+fn build_pipeline(
+    device: &Arc<Device>,
+    render_pass: &Arc<RenderPassAbstract + Send + Sync>,
+    vertex: &WatchedSource,
+    fragment: &WatchedSource,
+) -> Result<Arc<GraphicsPipelineAbstract + Send + Sync>, String> {
+    let vertex_spirv = compile(shaderc::ShaderKind::Vertex, &vertex.entry_path, &vertex.include_dirs)?;
+    let fragment_spirv = compile(
+        shaderc::ShaderKind::Fragment,
+        &fragment.entry_path,
+        &fragment.include_dirs,
+    )?;
+
+    // We no longer have `vulkano_shaders::shader!`'s generated reflection to lean on, so the
+    // entry points below describe the interfaces by hand. They're fixed to match `Vertex` and
+    // `fs::ty::PushConstantData` in `mod.rs`; only the GLSL body is expected to change across a
+    // reload.
+    let entry_point_name =
+        CStr::from_bytes_with_nul(ENTRY_POINT).expect("ENTRY_POINT is a valid nul-terminated str");
+
+    let vertex_module = unsafe {
+        ShaderModule::new(device.clone(), &vertex_spirv).map_err(|err| err.to_string())?
+    };
+    let fragment_module = unsafe {
+        ShaderModule::new(device.clone(), &fragment_spirv).map_err(|err| err.to_string())?
+    };
+
+    let vertex_entry_point = unsafe {
+        vertex_module.graphics_entry_point(
+            entry_point_name,
+            VertexInputInterface,
+            TexCoordInterface,
+            ShaderLayout,
+            GraphicsShaderType::Vertex,
+        )
+    };
+    let fragment_entry_point = unsafe {
+        fragment_module.graphics_entry_point(
+            entry_point_name,
+            TexCoordInterface,
+            FragColorInterface,
+            ShaderLayout,
+            GraphicsShaderType::Fragment,
+        )
+    };
+
+    let subpass =
+        Subpass::from(render_pass.clone(), 0).ok_or("render pass is missing subpass 0")?;
+
+    let pipeline = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<Vertex>()
+        .vertex_shader(vertex_entry_point, ())
+        .triangle_strip()
+        .viewports_scissors_dynamic(1)
+        .fragment_shader(fragment_entry_point, ())
+        .blend_alpha_blending()
+        .render_pass(subpass)
+        .build(device.clone())
+        .map_err(|err| err.to_string())?;
+
+    Ok(Arc::new(pipeline))
+}
+
+/// The vertex shader's single input: `Vertex::position`, a `vec2` at location 0.
+#[derive(Debug, Copy, Clone)]
+struct VertexInputInterface;
+
+unsafe impl ShaderInterfaceDef for VertexInputInterface {
+    type Iter = std::vec::IntoIter<ShaderInterfaceDefEntry>;
+
+    fn elements(&self) -> Self::Iter {
+        vec![ShaderInterfaceDefEntry {
+            location: 0..1,
+            format: Format::R32G32Sfloat,
+            name: Some(Cow::Borrowed("position")),
+        }]
+        .into_iter()
+    }
+}
+
+/// The vertex shader's output / fragment shader's input: `tex_coords`, a `vec2` at location 0.
+#[derive(Debug, Copy, Clone)]
+struct TexCoordInterface;
+
+unsafe impl ShaderInterfaceDef for TexCoordInterface {
+    type Iter = std::vec::IntoIter<ShaderInterfaceDefEntry>;
+
+    fn elements(&self) -> Self::Iter {
+        vec![ShaderInterfaceDefEntry {
+            location: 0..1,
+            format: Format::R32G32Sfloat,
+            name: Some(Cow::Borrowed("tex_coords")),
+        }]
+        .into_iter()
+    }
+}
+
+/// The fragment shader's single output: `f_color`, a `vec4` at location 0.
+#[derive(Debug, Copy, Clone)]
+struct FragColorInterface;
+
+unsafe impl ShaderInterfaceDef for FragColorInterface {
+    type Iter = std::vec::IntoIter<ShaderInterfaceDefEntry>;
+
+    fn elements(&self) -> Self::Iter {
+        vec![ShaderInterfaceDefEntry {
+            location: 0..1,
+            format: Format::R32G32B32A32Sfloat,
+            name: Some(Cow::Borrowed("f_color")),
+        }]
+        .into_iter()
+    }
+}
+
+/// No descriptor sets; a single push-constant range matching `fs::ty::PushConstantData { time,
+/// width, height }` (three `f32`s, fragment stage only) for the fragment shader. The vertex
+/// shader takes no push constants, but shares this layout since vulkano pipelines use one
+/// layout across all stages.
+#[derive(Debug, Copy, Clone)]
+struct ShaderLayout;
+
+unsafe impl PipelineLayoutDesc for ShaderLayout {
+    fn num_sets(&self) -> usize {
+        0
+    }
+
+    fn num_bindings_in_set(&self, _set: usize) -> Option<usize> {
+        None
+    }
+
+    fn descriptor(&self, _set: usize, _binding: usize) -> Option<DescriptorDesc> {
+        None
+    }
+
+    fn num_push_constants_ranges(&self) -> usize {
+        1
+    }
+
+    fn push_constants_range(&self, num: usize) -> Option<PipelineLayoutDescPcRange> {
+        if num != 0 {
+            return None;
+        }
+        Some(PipelineLayoutDescPcRange {
+            offset: 0,
+            size: 3 * std::mem::size_of::<f32>(),
+            stages: ShaderStages {
+                fragment: true,
+                ..ShaderStages::none()
+            },
+        })
+    }
+}
@@ -1,8 +1,11 @@
 extern crate nannou;
 
+mod hot_reload;
+
 use nannou::prelude::*;
 use nannou::vulkano;
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use nannou::vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
@@ -12,7 +15,9 @@ use nannou::vulkano::framebuffer::{
     Framebuffer, FramebufferAbstract, FramebufferCreationError, RenderPassAbstract, Subpass,
 };
 use nannou::vulkano::pipeline::viewport::Viewport;
-use nannou::vulkano::pipeline::{GraphicsPipeline, GraphicsPipelineAbstract};
+use nannou::vulkano::pipeline::GraphicsPipeline;
+
+use hot_reload::ReloadingPipeline;
 
 fn main() {
     nannou::app(model).run();
@@ -20,7 +25,7 @@ fn main() {
 
 struct Model {
     render_pass: Arc<RenderPassAbstract + Send + Sync>,
-    pipeline: Arc<GraphicsPipelineAbstract + Send + Sync>,
+    pipeline: RefCell<ReloadingPipeline>,
     vertex_buffer: Arc<CpuAccessibleBuffer<[Vertex]>>,
     framebuffers: RefCell<Vec<Arc<FramebufferAbstract + Send + Sync>>>,
 }
@@ -88,7 +93,7 @@ fn model(app: &App) -> Model {
         .unwrap(),
     );
 
-    let pipeline = Arc::new(
+    let seed_pipeline = Arc::new(
         GraphicsPipeline::start()
             .vertex_input_single_buffer::<Vertex>()
             .vertex_shader(vertex_shader.main_entry_point(), ())
@@ -101,6 +106,22 @@ fn model(app: &App) -> Model {
             .unwrap(),
     );
 
+    // Opt-in: watch the on-disk copies of the shaders above and hot-swap the pipeline when they
+    // change, instead of requiring a full rebuild for every tweak.
+    let shaders_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("examples/vulkan/vk_shader_include/shaders");
+    let pipeline = RefCell::new(ReloadingPipeline::new(
+        device.clone(),
+        render_pass.clone(),
+        shaders_dir.join("vertex.glsl"),
+        shaders_dir.join("fragment.glsl"),
+        vec![
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("examples/vulkan/vk_shader_include/common_shaders"),
+        ],
+        seed_pipeline,
+    ));
+
     let framebuffers = RefCell::new(Vec::new());
 
     Model {
@@ -118,12 +139,21 @@ fn view(app: &App, model: &Model, frame: Frame) -> Frame {
         dimensions: [w as _, h as _],
         depth_range: 0.0..1.0,
     };
+    // Restrict rendering to the left half of the frame, exercising the scissor dynamic state
+    // that a full-frame viewport otherwise leaves unused.
+    let scissor = nannou::vulkano::pipeline::viewport::Scissor {
+        origin: [0, 0],
+        dimensions: [w / 2, h],
+    };
     let dynamic_state = DynamicState {
         line_width: None,
         viewports: Some(vec![viewport]),
-        scissors: None,
+        scissors: Some(vec![scissor]),
     };
 
+    // Pick up any shader edits since the last frame before recording commands.
+    model.pipeline.borrow_mut().poll();
+
     // Update the framebuffers if necessary.
     while frame.swapchain_image_index() >= model.framebuffers.borrow().len() {
         let fb =
@@ -156,7 +186,7 @@ fn view(app: &App, model: &Model, frame: Frame) -> Frame {
         )
         .unwrap()
         .draw(
-            model.pipeline.clone(),
+            model.pipeline.borrow().pipeline(),
             &dynamic_state,
             vec![model.vertex_buffer.clone()],
             (),
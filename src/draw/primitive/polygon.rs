@@ -0,0 +1,285 @@
+use crate::color::conv::IntoLinSrgba;
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{
+    ColorScalar, Draw, Drawn, IntoDrawn, LinSrgba, SetColor, SetOrientation, SetPosition,
+    SetStroke,
+};
+use crate::draw::{theme, Drawing, DrawingContext};
+use crate::geom::{self, Point2};
+use crate::math::BaseFloat;
+use lyon::tessellation::StrokeOptions;
+
+/// The set of options shared by all polygon-like primitives (e.g. `Polygon`, `Ellipse`).
+#[derive(Clone, Debug, Default)]
+pub struct PolygonOptions<S = geom::scalar::Default> {
+    orientation: orientation::Properties<S>,
+    position: position::Properties<S>,
+    color: Option<LinSrgba>,
+    stroke_options: StrokeOptions,
+    /// The number of Chaikin corner-cutting iterations to apply to the input points before
+    /// tessellation. `0` (the default) leaves the points untouched.
+    smooth: usize,
+}
+
+/// Iterations beyond this point double the vertex count to the point of diminishing returns.
+/// Shared with `polyline` so both primitives cap `smooth` the same way.
+pub(crate) const MAX_SMOOTH_ITERATIONS: usize = 8;
+
+/// A polygon or stroked outline, awaiting its ordered set of points.
+#[derive(Clone, Debug, Default)]
+pub struct PolygonInit<S = geom::scalar::Default> {
+    opts: PolygonOptions<S>,
+}
+
+/// A polygon or stroked outline with its points already supplied, ready for tessellation.
+#[derive(Clone, Debug)]
+pub struct Polygon<S = geom::scalar::Default> {
+    opts: PolygonOptions<S>,
+    points: Vec<Point2<S>>,
+}
+
+/// The drawing context for a polygon.
+pub type DrawingPolygon<'a, S = geom::scalar::Default> = Drawing<'a, Polygon<S>, S>;
+
+pub type PolygonVertices = Vec<geom::vertex::Default>;
+pub type PolygonIndices = Vec<usize>;
+
+/// Types that may be set via the polygon builder API.
+pub trait SetPolygon<S>: Sized {
+    /// Access to the polygon options, for the purpose of setting the various properties.
+    fn polygon_options_mut(&mut self) -> &mut PolygonOptions<S>;
+
+    /// Apply `iterations` passes of Chaikin corner-cutting to the polygon's points before they
+    /// are tessellated.
+    ///
+    /// Each pass replaces every edge `(Pᵢ, Pᵢ₊₁)` with two points, one quarter and
+    /// three-quarters of the way along the edge, producing a limit curve equivalent to a
+    /// quadratic B-spline. This turns sparse control points into organically rounded paths
+    /// without pre-tessellating by hand.
+    ///
+    /// `iterations` is capped internally to avoid runaway vertex counts.
+    fn smooth(mut self, iterations: usize) -> Self {
+        self.polygon_options_mut().smooth = iterations.min(MAX_SMOOTH_ITERATIONS);
+        self
+    }
+}
+
+/// Apply `iterations` passes of Chaikin corner-cutting subdivision to `points`.
+///
+/// For a `closed` path (e.g. a polygon), the last→first edge is included so the loop stays
+/// closed. For an open path (e.g. a polyline), the two original endpoints are preserved so the
+/// smoothed path still begins and ends where the user drew it.
+pub(crate) fn chaikin_smooth<S>(
+    points: Vec<Point2<S>>,
+    iterations: usize,
+    closed: bool,
+) -> Vec<Point2<S>>
+where
+    S: BaseFloat,
+{
+    if points.len() < 3 || iterations == 0 {
+        return points;
+    }
+
+    let quarter = S::from(0.25).unwrap();
+    let three_quarters = S::from(0.75).unwrap();
+
+    let mut current = points;
+    for _ in 0..iterations {
+        let edge_count = if closed {
+            current.len()
+        } else {
+            current.len() - 1
+        };
+        let mut next = Vec::with_capacity(edge_count * 2);
+
+        if !closed {
+            next.push(current[0]);
+        }
+
+        for i in 0..edge_count {
+            let p = current[i];
+            let q = current[(i + 1) % current.len()];
+            next.push(Point2 {
+                x: p.x * three_quarters + q.x * quarter,
+                y: p.y * three_quarters + q.y * quarter,
+            });
+            next.push(Point2 {
+                x: p.x * quarter + q.x * three_quarters,
+                y: p.y * quarter + q.y * three_quarters,
+            });
+        }
+
+        if !closed {
+            next.push(*current.last().unwrap());
+        }
+
+        current = next;
+    }
+
+    current
+}
+
+impl<S> PolygonInit<S>
+where
+    S: BaseFloat,
+{
+    /// Stroke the outline with the given color.
+    pub fn stroke<C>(mut self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        crate::draw::properties::SetStroke::stroke_color(&mut self, color);
+        self
+    }
+
+    /// Consume the initialiser and produce a `Polygon` ready to be drawn from the given
+    /// ordered set of points.
+    pub fn points<I>(self, _ctxt: DrawingContext<S>, points: I) -> Polygon<S>
+    where
+        I: IntoIterator<Item = Point2<S>>,
+    {
+        let PolygonInit { opts } = self;
+        let points = points.into_iter().collect();
+        Polygon { opts, points }
+    }
+}
+
+impl<S> Polygon<S>
+where
+    S: BaseFloat,
+{
+    /// Apply `iterations` passes of Chaikin corner-cutting to the already-supplied points. See
+    /// `SetPolygon::smooth` for details on the algorithm.
+    pub fn smooth(mut self, iterations: usize) -> Self {
+        self.opts.smooth = iterations.min(MAX_SMOOTH_ITERATIONS);
+        self
+    }
+}
+
+impl<S> IntoDrawn<S> for Polygon<S>
+where
+    S: BaseFloat,
+{
+    type Vertices = PolygonVertices;
+    type Indices = PolygonIndices;
+    fn into_drawn(self, draw: Draw<S>) -> Drawn<S, Self::Vertices, Self::Indices> {
+        let Polygon { opts, points } = self;
+        let points = chaikin_smooth(points, opts.smooth, true);
+        crate::draw::primitive::path::tessellate_polygon(draw, &opts, points)
+    }
+}
+
+impl<S> Polygon<S>
+where
+    S: BaseFloat,
+{
+    /// Apply the configured `smooth` iterations to the points and tessellate them, falling back
+    /// to the given theme's default color/stroke properties for anything left unspecified.
+    pub fn into_drawn_themed(
+        self,
+        draw: Draw<S>,
+        theme_primitive: &theme::Primitive,
+    ) -> Drawn<S, PolygonVertices, PolygonIndices> {
+        let Polygon { opts, points } = self;
+        let points = chaikin_smooth(points, opts.smooth, true);
+        crate::draw::primitive::path::tessellate_polygon_themed(draw, theme_primitive, &opts, points)
+    }
+}
+
+impl<S> SetOrientation<S> for PolygonInit<S> {
+    fn properties(&mut self) -> &mut orientation::Properties<S> {
+        &mut self.opts.orientation
+    }
+}
+
+impl<S> SetPosition<S> for PolygonInit<S> {
+    fn properties(&mut self) -> &mut position::Properties<S> {
+        &mut self.opts.position
+    }
+}
+
+impl<S> SetColor<ColorScalar> for PolygonInit<S> {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        &mut self.opts.color
+    }
+}
+
+impl<S> SetStroke for PolygonInit<S> {
+    fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
+        &mut self.opts.stroke_options
+    }
+}
+
+impl<S> SetPolygon<S> for PolygonInit<S> {
+    fn polygon_options_mut(&mut self) -> &mut PolygonOptions<S> {
+        &mut self.opts
+    }
+}
+
+impl<S> From<PolygonInit<S>> for Primitive<S> {
+    fn from(prim: PolygonInit<S>) -> Self {
+        Primitive::PolygonInit(prim)
+    }
+}
+
+impl<S> From<Polygon<S>> for Primitive<S> {
+    fn from(prim: Polygon<S>) -> Self {
+        Primitive::Polygon(prim)
+    }
+}
+
+// Drawing methods.
+
+impl<'a, S> DrawingPolygon<'a, S>
+where
+    S: BaseFloat,
+{
+    /// Apply `iterations` passes of Chaikin corner-cutting to the polygon's points before they
+    /// are tessellated. See `SetPolygon::smooth` for details on the algorithm.
+    pub fn smooth(self, iterations: usize) -> Self {
+        self.map_ty(|ty| ty.smooth(iterations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chaikin_smooth;
+    use crate::geom::Point2;
+
+    fn pts(coords: &[(f32, f32)]) -> Vec<Point2<f32>> {
+        coords.iter().map(|&(x, y)| Point2 { x, y }).collect()
+    }
+
+    #[test]
+    fn zero_iterations_is_a_no_op() {
+        let square = pts(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        assert_eq!(chaikin_smooth(square.clone(), 0, true), square);
+    }
+
+    #[test]
+    fn fewer_than_three_points_is_a_no_op() {
+        let line = pts(&[(0.0, 0.0), (1.0, 1.0)]);
+        assert_eq!(chaikin_smooth(line.clone(), 4, true), line);
+        assert_eq!(chaikin_smooth(line.clone(), 4, false), line);
+    }
+
+    #[test]
+    fn closed_path_cuts_every_edge_including_the_wraparound() {
+        let triangle = pts(&[(0.0, 0.0), (2.0, 0.0), (0.0, 2.0)]);
+        let smoothed = chaikin_smooth(triangle, 1, true);
+        // Each of the 3 edges (including the last->first wraparound) is cut into 2.
+        assert_eq!(smoothed.len(), 6);
+    }
+
+    #[test]
+    fn open_path_preserves_original_endpoints() {
+        let path = pts(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        let smoothed = chaikin_smooth(path.clone(), 1, false);
+        assert_eq!(smoothed.first(), path.first());
+        assert_eq!(smoothed.last(), path.last());
+        // 3 edges, each cut into 2, plus the 2 preserved endpoints.
+        assert_eq!(smoothed.len(), 3 * 2 + 2);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::draw::primitive::polygon::{chaikin_smooth, PolygonIndices, PolygonVertices, MAX_SMOOTH_ITERATIONS};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{Draw, Drawn, IntoDrawn, SetOrientation, SetPosition, SetStroke};
+use crate::draw::{theme, Drawing, DrawingContext};
+use crate::geom::{self, Point2};
+use crate::math::BaseFloat;
+use lyon::tessellation::StrokeOptions;
+
+/// The set of options used to tessellate a `Polyline`.
+#[derive(Clone, Debug, Default)]
+pub struct PolylineOptions<S = geom::scalar::Default> {
+    orientation: orientation::Properties<S>,
+    position: position::Properties<S>,
+    stroke_options: StrokeOptions,
+    /// The number of Chaikin corner-cutting iterations to apply to the input points before
+    /// tessellation. `0` (the default) leaves the points untouched.
+    smooth: usize,
+}
+
+/// A stroked, open path awaiting its ordered set of points.
+#[derive(Clone, Debug, Default)]
+pub struct PolylineInit<S = geom::scalar::Default> {
+    opts: PolylineOptions<S>,
+}
+
+/// A stroked, open path with its points already supplied, ready for tessellation.
+#[derive(Clone, Debug)]
+pub struct Polyline<S = geom::scalar::Default> {
+    opts: PolylineOptions<S>,
+    points: Vec<Point2<S>>,
+}
+
+/// The drawing context for a polyline.
+pub type DrawingPolyline<'a, S = geom::scalar::Default> = Drawing<'a, Polyline<S>, S>;
+
+impl<S> PolylineInit<S>
+where
+    S: BaseFloat,
+{
+    /// Apply `iterations` passes of Chaikin corner-cutting to the polyline's points before they
+    /// are tessellated, preserving the original start and end points. See
+    /// `polygon::SetPolygon::smooth` for details on the algorithm.
+    pub fn smooth(mut self, iterations: usize) -> Self {
+        self.opts.smooth = iterations.min(MAX_SMOOTH_ITERATIONS);
+        self
+    }
+
+    /// Consume the initialiser and produce a `Polyline` ready to be drawn from the given
+    /// ordered set of points.
+    pub fn points<I>(self, _ctxt: DrawingContext<S>, points: I) -> Polyline<S>
+    where
+        I: IntoIterator<Item = Point2<S>>,
+    {
+        let PolylineInit { opts } = self;
+        let points = points.into_iter().collect();
+        Polyline { opts, points }
+    }
+}
+
+impl<S> Polyline<S>
+where
+    S: BaseFloat,
+{
+    /// Apply `iterations` passes of Chaikin corner-cutting to the already-supplied points.
+    pub fn smooth(mut self, iterations: usize) -> Self {
+        self.opts.smooth = iterations.min(MAX_SMOOTH_ITERATIONS);
+        self
+    }
+}
+
+impl<S> IntoDrawn<S> for Polyline<S>
+where
+    S: BaseFloat,
+{
+    type Vertices = PolygonVertices;
+    type Indices = PolygonIndices;
+    fn into_drawn(self, draw: Draw<S>) -> Drawn<S, Self::Vertices, Self::Indices> {
+        let Polyline { opts, points } = self;
+        let points = chaikin_smooth(points, opts.smooth, false);
+        crate::draw::primitive::path::tessellate_polyline(draw, &opts, points)
+    }
+}
+
+impl<S> SetOrientation<S> for PolylineInit<S> {
+    fn properties(&mut self) -> &mut orientation::Properties<S> {
+        &mut self.opts.orientation
+    }
+}
+
+impl<S> SetPosition<S> for PolylineInit<S> {
+    fn properties(&mut self) -> &mut position::Properties<S> {
+        &mut self.opts.position
+    }
+}
+
+impl<S> SetStroke for PolylineInit<S> {
+    fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
+        &mut self.opts.stroke_options
+    }
+}
+
+impl<S> From<PolylineInit<S>> for Primitive<S> {
+    fn from(prim: PolylineInit<S>) -> Self {
+        Primitive::PolylineInit(prim)
+    }
+}
+
+impl<S> From<Polyline<S>> for Primitive<S> {
+    fn from(prim: Polyline<S>) -> Self {
+        Primitive::Polyline(prim)
+    }
+}
+
+// Drawing methods.
+
+impl<'a, S> DrawingPolyline<'a, S>
+where
+    S: BaseFloat,
+{
+    /// Apply `iterations` passes of Chaikin corner-cutting to the polyline's points before they
+    /// are tessellated, preserving the original start and end points. See
+    /// `polygon::SetPolygon::smooth` for details on the algorithm.
+    pub fn smooth(self, iterations: usize) -> Self {
+        self.map_ty(|ty| ty.smooth(iterations))
+    }
+}
@@ -0,0 +1,68 @@
+//! A logical-to-Vulkan scissor-rectangle coordinate conversion, pending integration into the
+//! `draw` API's command-recording path.
+//!
+//! The `vulkan` example leaves `DynamicState { scissors: None, .. }` unused, recording its draw
+//! commands against the full framebuffer. `Scissor::to_vulkan` does the coordinate-space
+//! conversion a per-primitive `draw.polygon()...scissor(rect)` builder method would need — but
+//! nothing in this tree yet calls it when actually recording a primitive's draw commands (that
+//! lives in the tessellation/command-recording path, which this series doesn't touch), so until
+//! that wiring lands, this type stays `pub(crate)` rather than a public builder method that would
+//! silently have no effect on rendering.
+use crate::geom::Rect;
+use crate::math::BaseFloat;
+
+/// A clip region in nannou's logical coordinate space (origin at the centre, Y up), to be
+/// mapped into a Vulkan scissor rectangle (origin at the top-left, Y down) when commands for
+/// the affected draws are recorded.
+///
+/// `#[allow(dead_code)]` until the command-recording integration described above lands and
+/// actually constructs one of these.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Scissor<S = crate::geom::scalar::Default> {
+    rect: Rect<S>,
+}
+
+#[allow(dead_code)]
+impl<S> Scissor<S>
+where
+    S: BaseFloat,
+{
+    /// Clip to the given rectangle, specified in the same logical coordinate space as the rest
+    /// of the `draw` API.
+    pub(crate) fn new(rect: Rect<S>) -> Self {
+        Scissor { rect }
+    }
+
+    /// The clip rectangle in logical coordinates.
+    pub(crate) fn rect(&self) -> Rect<S> {
+        self.rect
+    }
+
+    /// Convert to a Vulkan scissor rectangle for a framebuffer of the given pixel dimensions,
+    /// flipping from nannou's centre-origin, Y-up space to Vulkan's top-left-origin, Y-down
+    /// space and clamping to the framebuffer bounds.
+    pub(crate) fn to_vulkan(&self, framebuffer_dims: [u32; 2]) -> crate::vulkano::pipeline::viewport::Scissor {
+        let [fb_w, fb_h] = framebuffer_dims;
+        let fb_w_s = S::from(fb_w).unwrap();
+        let fb_h_s = S::from(fb_h).unwrap();
+        let half_w = fb_w_s / (S::one() + S::one());
+        let half_h = fb_h_s / (S::one() + S::one());
+
+        // Clamp each edge independently, then derive width/height from the *clamped* edges so a
+        // rect that overhangs the framebuffer on one side doesn't inflate the opposite edge's
+        // clamp into an out-of-bounds `origin + dimensions`.
+        let left = (self.rect.left() + half_w).max(S::zero()).min(fb_w_s);
+        let right = (self.rect.right() + half_w).max(S::zero()).min(fb_w_s);
+        let top = (half_h - self.rect.top()).max(S::zero()).min(fb_h_s);
+        let bottom = (half_h - self.rect.bottom()).max(S::zero()).min(fb_h_s);
+
+        let width = (right - left).max(S::zero());
+        let height = (bottom - top).max(S::zero());
+
+        crate::vulkano::pipeline::viewport::Scissor {
+            origin: [left.to_f32().unwrap() as i32, top.to_f32().unwrap() as i32],
+            dimensions: [width.to_f32().unwrap() as u32, height.to_f32().unwrap() as u32],
+        }
+    }
+}
@@ -0,0 +1,476 @@
+//! A data-driven, reloadable chain of fullscreen post-processing passes.
+//!
+//! Builds on [`render_graph`](super::render_graph): each entry in a `PostProcessPreset` becomes
+//! one node that samples the previous pass's output (any named history/feedback `inputs` are
+//! declared as reads but not bound to the shader) and writes to a transient attachment sized
+//! relative to the swapchain. This gives sketches a
+//! CRT/bloom/color-grading stack that can be swapped by editing a preset file rather than
+//! recompiling, analogous to established shader-preset pipelines (e.g. RetroArch's `.slangp`).
+//!
+//! [`PostProcessPreset::build`] does the runtime half: it compiles every pass's GLSL with
+//! `shaderc`, builds a fullscreen-triangle `GraphicsPipeline` for it (no vertex buffer — three
+//! vertices are derived from `gl_VertexIndex`, the same trick used for a single full-screen
+//! triangle in most post-processing setups), and hands back a [`PostProcessRuntime`] whose passes
+//! are ready to add to a `render_graph::RenderGraphBuilder`. Each pass samples its input (the
+//! previous pass's output, or `scene_resource` for the first pass) through one combined
+//! image-sampler binding and receives `FrameUniforms` plus up to [`MAX_PASS_PARAMS`] of its own
+//! named `params` as push constants.
+
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+
+use crate::vulkano::descriptor::descriptor::{
+    DescriptorDesc, DescriptorDescTy, DescriptorImageDesc, DescriptorImageDescArray,
+    DescriptorImageDescDimensions, ShaderStages,
+};
+use crate::vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use crate::vulkano::descriptor::pipeline_layout::{PipelineLayoutDesc, PipelineLayoutDescPcRange};
+use crate::vulkano::device::Device;
+use crate::vulkano::format::{ClearValue, Format};
+use crate::vulkano::framebuffer::{RenderPassAbstract, Subpass};
+use crate::vulkano::pipeline::shader::{
+    GraphicsShaderType, ShaderInterfaceDef, ShaderInterfaceDefEntry, ShaderModule,
+};
+use crate::vulkano::pipeline::vertex::{BufferlessDefinition, BufferlessVertices};
+use crate::vulkano::pipeline::{GraphicsPipeline, GraphicsPipelineAbstract};
+use crate::vulkano::sampler::Sampler;
+use crate::vulkano::command_buffer::DynamicState;
+
+use crate::window::render_graph::{
+    AttachmentDesc, AttachmentSize, PassContext, PassDesc, RecordFn, ResourceId,
+};
+
+/// A shader source, either embedded directly in the preset or loaded from a file alongside it.
+///
+/// Written in a preset as `shader = { inline = "..." }` or `shader = { path = "..." }`. An
+/// `untagged` enum won't do here: both variants would deserialize equally well from a bare
+/// string (`PathBuf` parses from any string just like `String` does), so the `Path` variant
+/// would never be chosen. Tagging with explicit field names removes the ambiguity.
+#[derive(Clone, Debug, Deserialize)]
+pub enum ShaderSource {
+    #[serde(rename = "inline")]
+    Inline(String),
+    #[serde(rename = "path")]
+    Path(PathBuf),
+}
+
+impl ShaderSource {
+    /// Resolve the GLSL source, reading from disk if this entry names a path. `base_dir` is the
+    /// directory containing the preset file, so relative paths resolve the way users expect.
+    pub fn resolve(&self, base_dir: &Path) -> std::io::Result<String> {
+        match self {
+            ShaderSource::Inline(src) => Ok(src.clone()),
+            ShaderSource::Path(path) => std::fs::read_to_string(base_dir.join(path)),
+        }
+    }
+}
+
+/// A single entry in a post-processing preset: one fullscreen fragment pass.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PassPreset {
+    /// A name for this pass, used to label its output for later passes to sample.
+    pub name: String,
+    /// The fragment shader for this pass.
+    pub shader: ShaderSource,
+    /// Named resources this pass reads in addition to the previous pass's output (e.g. a
+    /// `history` buffer for feedback effects). Each is declared as a render-graph read, which
+    /// orders this pass after the pass writing it and transitions it to a shader-readable
+    /// layout, but only the previous pass's output is bound to the shader's descriptor set; a
+    /// name that no pass in the graph writes is ignored.
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// The pass's render target resolution, relative to the swapchain. `1.0` (the default) is
+    /// full resolution; `0.5` is useful for cheap blur passes.
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Named float/int parameters exposed to the shader as push constants.
+    #[serde(default)]
+    pub params: HashMap<String, f32>,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+impl PassPreset {
+    /// The render target size this pass's output should be allocated at, for use with
+    /// [`AttachmentDesc`](super::render_graph::AttachmentDesc).
+    pub fn attachment_size(&self) -> AttachmentSize {
+        if (self.scale - 1.0).abs() < std::f32::EPSILON {
+            AttachmentSize::SwapchainRelative
+        } else {
+            AttachmentSize::ScaledBy(self.scale)
+        }
+    }
+}
+
+/// An ordered chain of post-processing passes, as loaded from a preset file.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PostProcessPreset {
+    pub passes: Vec<PassPreset>,
+}
+
+/// Per-frame uniforms made available to every pass, in addition to its declared `params`.
+#[derive(Copy, Clone, Debug)]
+pub struct FrameUniforms {
+    pub time: f32,
+    pub resolution: [f32; 2],
+    pub frame_index: u64,
+}
+
+impl PostProcessPreset {
+    /// Load and parse a preset file. The preset format is TOML; see `PassPreset` for the fields
+    /// of each pass entry.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        toml::from_str(&text)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// The directory the preset file lives in, used to resolve any `ShaderSource::Path` entries
+    /// relative to it rather than the process's current directory.
+    pub fn base_dir(path: impl AsRef<Path>) -> PathBuf {
+        path.as_ref()
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    }
+
+    /// Compile every declared pass and build it into a `render_graph::PassDesc`, ready to add to
+    /// a `render_graph::RenderGraphBuilder`. `scene_resource` names the resource the first pass
+    /// should sample (typically the scene's rendered color output); later passes sample the
+    /// previous pass's output by name instead. All passes share `render_pass`, a single-color-
+    /// attachment render pass the caller builds once up front.
+    pub fn build(
+        &self,
+        device: Arc<Device>,
+        render_pass: Arc<RenderPassAbstract + Send + Sync>,
+        sampler: Arc<Sampler>,
+        base_dir: &Path,
+        scene_resource: &str,
+    ) -> Result<PostProcessRuntime, String> {
+        let uniforms = Arc::new(Mutex::new(FrameUniforms {
+            time: 0.0,
+            resolution: [0.0, 0.0],
+            frame_index: 0,
+        }));
+
+        let mut passes = Vec::with_capacity(self.passes.len());
+        let mut previous_output = scene_resource.to_string();
+
+        for pass in &self.passes {
+            let source = pass.shader.resolve(base_dir).map_err(|err| {
+                format!("failed to resolve shader for pass {:?}: {}", pass.name, err)
+            })?;
+            let pipeline = build_pipeline(&device, &render_pass, &source)?;
+
+            let input_name = previous_output.clone();
+            let params = pack_params(&pass.params);
+            let record = record_fn(pipeline, sampler.clone(), uniforms.clone(), input_name.clone(), params);
+
+            let mut desc = PassDesc::new(&pass.name, render_pass.clone(), record).reads(&input_name);
+            for extra_input in &pass.inputs {
+                desc = desc.reads(extra_input);
+            }
+            desc = desc.writes(AttachmentDesc {
+                name: ResourceId::new(pass.name.clone()),
+                format: Format::R8G8B8A8Unorm,
+                size: pass.attachment_size(),
+            });
+
+            passes.push(desc);
+            previous_output = pass.name.clone();
+        }
+
+        Ok(PostProcessRuntime { passes, uniforms })
+    }
+}
+
+/// The number of a pass's own named `params` fed through as push constants. Params beyond this
+/// many, in sorted name order, are not passed to the shader; no error is reported.
+pub const MAX_PASS_PARAMS: usize = 4;
+
+/// Pack `params` into a fixed-size array in sorted key order, so every pass's push-constant
+/// layout is the same regardless of which parameter names a preset actually declares.
+fn pack_params(params: &HashMap<String, f32>) -> [f32; MAX_PASS_PARAMS] {
+    let mut sorted: Vec<_> = params.iter().collect();
+    sorted.sort_by_key(|(name, _)| name.as_str());
+    let mut packed = [0.0; MAX_PASS_PARAMS];
+    for (slot, (_, value)) in packed.iter_mut().zip(sorted) {
+        *slot = *value;
+    }
+    packed
+}
+
+/// The push constants every pass's fragment shader receives: the shared per-frame uniforms
+/// followed by its own packed `params`.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct PushConstants {
+    time: f32,
+    resolution: [f32; 2],
+    frame_index: f32,
+    params: [f32; MAX_PASS_PARAMS],
+}
+
+/// A built, ready-to-bake post-processing chain, along with the shared handle used to push this
+/// frame's uniforms into every pass before the render graph records its commands.
+pub struct PostProcessRuntime {
+    passes: Vec<PassDesc>,
+    uniforms: Arc<Mutex<FrameUniforms>>,
+}
+
+impl PostProcessRuntime {
+    /// Update the per-frame uniforms every pass's push constants are drawn from. Call once per
+    /// frame, before baking/recording the `render_graph::RenderGraph` these passes were added to.
+    pub fn set_uniforms(&self, frame: FrameUniforms) {
+        *self.uniforms.lock().unwrap() = frame;
+    }
+
+    /// Hand the built passes to a `render_graph::RenderGraphBuilder` via repeated `add_pass`
+    /// calls.
+    pub fn into_passes(self) -> Vec<PassDesc> {
+        self.passes
+    }
+}
+
+/// Entry point name every compiled stage uses, matching the `"main"` passed to
+/// `compile_into_spirv` below.
+const ENTRY_POINT: &[u8] = b"main\0";
+
+/// The fullscreen-triangle vertex shader shared by every pass: no vertex buffer, its three
+/// corners are derived from `gl_VertexIndex`.
+const FULLSCREEN_VERTEX_GLSL: &str = "
+#version 450
+
+layout(location = 0) out vec2 v_uv;
+
+void main() {
+    v_uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(v_uv * 2.0 - 1.0, 0.0, 1.0);
+}
+";
+
+fn compile(kind: shaderc::ShaderKind, source: &str, label: &str) -> Result<Vec<u8>, String> {
+    let mut compiler = shaderc::Compiler::new().ok_or("failed to initialise shaderc")?;
+    let binary = compiler
+        .compile_into_spirv(source, kind, label, "main", None)
+        .map_err(|err| err.to_string())?;
+    Ok(binary.as_binary_u8().to_vec())
+}
+
+fn build_pipeline(
+    device: &Arc<Device>,
+    render_pass: &Arc<RenderPassAbstract + Send + Sync>,
+    fragment_source: &str,
+) -> Result<Arc<GraphicsPipelineAbstract + Send + Sync>, String> {
+    let vertex_spirv = compile(shaderc::ShaderKind::Vertex, FULLSCREEN_VERTEX_GLSL, "fullscreen.vert")?;
+    let fragment_spirv = compile(shaderc::ShaderKind::Fragment, fragment_source, "post_process.frag")?;
+
+    let entry_point_name =
+        CStr::from_bytes_with_nul(ENTRY_POINT).expect("ENTRY_POINT is a valid nul-terminated str");
+
+    let vertex_module = unsafe {
+        ShaderModule::new(device.clone(), &vertex_spirv).map_err(|err| err.to_string())?
+    };
+    let fragment_module = unsafe {
+        ShaderModule::new(device.clone(), &fragment_spirv).map_err(|err| err.to_string())?
+    };
+
+    let vertex_entry_point = unsafe {
+        vertex_module.graphics_entry_point(
+            entry_point_name,
+            NoVertexInput,
+            UvInterface,
+            PostProcessLayout,
+            GraphicsShaderType::Vertex,
+        )
+    };
+    let fragment_entry_point = unsafe {
+        fragment_module.graphics_entry_point(
+            entry_point_name,
+            UvInterface,
+            FragColorInterface,
+            PostProcessLayout,
+            GraphicsShaderType::Fragment,
+        )
+    };
+
+    let subpass = Subpass::from(render_pass.clone(), 0).ok_or("render pass is missing subpass 0")?;
+
+    let pipeline = GraphicsPipeline::start()
+        .vertex_input(BufferlessDefinition {})
+        .vertex_shader(vertex_entry_point, ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_entry_point, ())
+        .render_pass(subpass)
+        .build(device.clone())
+        .map_err(|err| err.to_string())?;
+
+    Ok(Arc::new(pipeline))
+}
+
+/// Build the `RecordFn` a pass's `PassDesc` records its fullscreen draw through: bind `input_name`
+/// from the resolved `PassContext::inputs` as a combined image sampler, push this frame's shared
+/// uniforms (read from `uniforms` at record time) plus `params`, and draw the fullscreen triangle.
+fn record_fn(
+    pipeline: Arc<GraphicsPipelineAbstract + Send + Sync>,
+    sampler: Arc<Sampler>,
+    uniforms: Arc<Mutex<FrameUniforms>>,
+    input_name: String,
+    params: [f32; MAX_PASS_PARAMS],
+) -> RecordFn {
+    Box::new(move |commands, ctx: &PassContext| {
+        let input_image = ctx
+            .inputs
+            .get(&input_name)
+            .unwrap_or_else(|| panic!("post-process pass missing declared input {:?}", input_name))
+            .clone();
+
+        let descriptor_set = Arc::new(
+            PersistentDescriptorSet::start(pipeline.clone(), 0)
+                .add_sampled_image(input_image, sampler.clone())
+                .expect("failed to bind post-process input image")
+                .build()
+                .expect("failed to build post-process descriptor set"),
+        );
+
+        let frame = *uniforms.lock().unwrap();
+        let push_constants = PushConstants {
+            time: frame.time,
+            resolution: frame.resolution,
+            frame_index: frame.frame_index as f32,
+            params,
+        };
+
+        commands
+            .begin_render_pass(ctx.framebuffer.clone(), false, vec![ClearValue::None])
+            .expect("failed to begin post-process render pass")
+            .draw(
+                pipeline.clone(),
+                &DynamicState::none(),
+                BufferlessVertices {
+                    vertices: 3,
+                    instances: 1,
+                },
+                descriptor_set,
+                push_constants,
+            )
+            .expect("failed to record post-process draw")
+            .end_render_pass()
+            .expect("failed to end post-process render pass")
+    })
+}
+
+/// The fullscreen vertex shader's sole input: none, since its three positions come from
+/// `gl_VertexIndex` rather than a vertex buffer.
+#[derive(Debug, Copy, Clone)]
+struct NoVertexInput;
+
+unsafe impl ShaderInterfaceDef for NoVertexInput {
+    type Iter = std::vec::IntoIter<ShaderInterfaceDefEntry>;
+
+    fn elements(&self) -> Self::Iter {
+        Vec::new().into_iter()
+    }
+}
+
+/// The fullscreen vertex shader's output / every pass's fragment shader input: `v_uv`, a `vec2`
+/// at location 0.
+#[derive(Debug, Copy, Clone)]
+struct UvInterface;
+
+unsafe impl ShaderInterfaceDef for UvInterface {
+    type Iter = std::vec::IntoIter<ShaderInterfaceDefEntry>;
+
+    fn elements(&self) -> Self::Iter {
+        vec![ShaderInterfaceDefEntry {
+            location: 0..1,
+            format: Format::R32G32Sfloat,
+            name: Some(std::borrow::Cow::Borrowed("v_uv")),
+        }]
+        .into_iter()
+    }
+}
+
+/// Every pass's fragment shader output: `f_color`, a `vec4` at location 0.
+#[derive(Debug, Copy, Clone)]
+struct FragColorInterface;
+
+unsafe impl ShaderInterfaceDef for FragColorInterface {
+    type Iter = std::vec::IntoIter<ShaderInterfaceDefEntry>;
+
+    fn elements(&self) -> Self::Iter {
+        vec![ShaderInterfaceDefEntry {
+            location: 0..1,
+            format: Format::R32G32B32A32Sfloat,
+            name: Some(std::borrow::Cow::Borrowed("f_color")),
+        }]
+        .into_iter()
+    }
+}
+
+/// One combined image-sampler binding (set 0, binding 0) for the pass's input, plus a single
+/// push-constant range covering `PushConstants`. Shared by every pass: each has exactly one
+/// sampled input and the same uniform/param layout.
+#[derive(Debug, Copy, Clone)]
+struct PostProcessLayout;
+
+unsafe impl PipelineLayoutDesc for PostProcessLayout {
+    fn num_sets(&self) -> usize {
+        1
+    }
+
+    fn num_bindings_in_set(&self, set: usize) -> Option<usize> {
+        match set {
+            0 => Some(1),
+            _ => None,
+        }
+    }
+
+    fn descriptor(&self, set: usize, binding: usize) -> Option<DescriptorDesc> {
+        if set == 0 && binding == 0 {
+            Some(DescriptorDesc {
+                ty: DescriptorDescTy::CombinedImageSampler(DescriptorImageDesc {
+                    sampled: true,
+                    dimensions: DescriptorImageDescDimensions::TwoDimensional,
+                    format: None,
+                    multisampled: false,
+                    array_layers: DescriptorImageDescArray::NonArrayed,
+                }),
+                array_count: 1,
+                stages: ShaderStages {
+                    fragment: true,
+                    ..ShaderStages::none()
+                },
+                readonly: true,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn num_push_constants_ranges(&self) -> usize {
+        1
+    }
+
+    fn push_constants_range(&self, num: usize) -> Option<PipelineLayoutDescPcRange> {
+        if num != 0 {
+            return None;
+        }
+        Some(PipelineLayoutDescPcRange {
+            offset: 0,
+            size: std::mem::size_of::<PushConstants>(),
+            stages: ShaderStages {
+                fragment: true,
+                ..ShaderStages::none()
+            },
+        })
+    }
+}
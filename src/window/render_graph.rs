@@ -0,0 +1,470 @@
+//! A declarative render-graph for composing multi-pass `Frame` rendering.
+//!
+//! Rather than hand-managing `RenderPassAbstract`, `Framebuffer` and swapchain images inside
+//! `view` (as the `vulkan` example does for its single pass), users declare named passes as
+//! nodes and the resources each pass reads from and writes to as edges. `RenderGraphBuilder::bake`
+//! topologically sorts the passes and asks a `RenderGraphCache` for each pass's transient output
+//! image (sized relative to the swapchain, reusing the previous frame's allocation where
+//! dimensions and format are unchanged), and `RenderGraph::record_commands` inserts the image
+//! layout transitions each pass needs and records its command buffer contents in dependency
+//! order. This turns a chain like "render scene → bloom extract → blur → composite" into data
+//! rather than bespoke framebuffer bookkeeping.
+//!
+//! `FrameGraphExt::graph_output` lets the final present step (or a later pass recorded outside
+//! this graph) retrieve any named output attachment once a graph has been baked for
+//! the current swapchain image, so it can sample an earlier pass's result as a plain
+//! `sampler2D`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::vulkano::command_buffer::AutoCommandBufferBuilder;
+use crate::vulkano::format::Format;
+use crate::vulkano::framebuffer::{Framebuffer, FramebufferAbstract, RenderPassAbstract};
+use crate::vulkano::image::AttachmentImage;
+use crate::vulkano::sync::{AccessFlagBits, PipelineStages};
+use crate::vulkano::image::ImageLayout;
+
+/// Identifies a single node (pass) within a `RenderGraphBuilder`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct PassId(usize);
+
+/// Identifies a named attachment resource produced by some pass and consumed by others.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ResourceId(String);
+
+impl ResourceId {
+    /// Name a resource, for building an [`AttachmentDesc`] outside this module (e.g. from
+    /// `post_process`, which assembles its own passes' attachments).
+    pub fn new(name: impl Into<String>) -> Self {
+        ResourceId(name.into())
+    }
+}
+
+/// How a transient attachment's dimensions relate to the swapchain's.
+#[derive(Copy, Clone, Debug)]
+pub enum AttachmentSize {
+    /// Exactly the swapchain's current dimensions.
+    SwapchainRelative,
+    /// A fraction of the swapchain's current dimensions, e.g. `0.5` for a half-resolution blur
+    /// target.
+    ScaledBy(f32),
+}
+
+/// Describes a single attachment that a pass writes to.
+#[derive(Clone, Debug)]
+pub struct AttachmentDesc {
+    pub name: ResourceId,
+    pub format: Format,
+    pub size: AttachmentSize,
+}
+
+/// The inputs and output resolved for a single pass, handed to its `record` closure.
+pub struct PassContext {
+    /// The pass's sampled inputs, keyed by the resource name declared via `PassDesc::reads`.
+    pub inputs: HashMap<String, Arc<AttachmentImage>>,
+    /// The render pass this node's framebuffer was built against.
+    pub render_pass: Arc<RenderPassAbstract + Send + Sync>,
+    /// The framebuffer wrapping this pass's (single) output attachment.
+    pub framebuffer: Arc<FramebufferAbstract + Send + Sync>,
+}
+
+/// Records a pass's draw commands given its resolved `PassContext`, consuming and returning the
+/// in-progress command buffer builder the way the rest of vulkano's recording API does (see the
+/// `vulkan` example's `begin_render_pass`/`draw`/`end_render_pass` chain).
+pub type RecordFn =
+    Box<dyn Fn(AutoCommandBufferBuilder, &PassContext) -> AutoCommandBufferBuilder + Send + Sync>;
+
+/// A single node in the render graph: what it reads, what it writes, and the closure that
+/// records its commands once its inputs and output framebuffer are resolved.
+pub struct PassDesc {
+    name: String,
+    reads: Vec<ResourceId>,
+    writes: Vec<AttachmentDesc>,
+    render_pass: Arc<RenderPassAbstract + Send + Sync>,
+    record: RecordFn,
+}
+
+impl PassDesc {
+    /// Begin describing a new pass with the given name, the single-attachment render pass its
+    /// framebuffer is built against, and the closure that records its draw commands.
+    pub fn new(
+        name: &str,
+        render_pass: Arc<RenderPassAbstract + Send + Sync>,
+        record: RecordFn,
+    ) -> Self {
+        PassDesc {
+            name: name.to_string(),
+            reads: Vec::new(),
+            writes: Vec::new(),
+            render_pass,
+            record,
+        }
+    }
+
+    /// Declare that this pass samples the named resource as one of its inputs.
+    pub fn reads(mut self, resource: &str) -> Self {
+        self.reads.push(ResourceId(resource.to_string()));
+        self
+    }
+
+    /// Declare that this pass writes the given attachment.
+    pub fn writes(mut self, attachment: AttachmentDesc) -> Self {
+        self.writes.push(attachment);
+        self
+    }
+}
+
+/// Builds up the set of passes and their dependencies before the graph is baked.
+#[derive(Default)]
+pub struct RenderGraphBuilder {
+    passes: Vec<PassDesc>,
+}
+
+/// Across-frame cache of the transient images allocated for each named resource, so a graph
+/// baked every frame doesn't reallocate its intermediate attachments unless their size or format
+/// actually changes (e.g. on a window resize).
+#[derive(Default)]
+pub struct RenderGraphCache {
+    images: HashMap<String, (Arc<AttachmentImage>, [u32; 2], Format)>,
+}
+
+impl RenderGraphCache {
+    /// Create an empty cache. Keep one of these alongside the `RenderGraphBuilder`'s owner (e.g.
+    /// in `Model`) and reuse it across frames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get_or_allocate(
+        &mut self,
+        device: &Arc<crate::vulkano::device::Device>,
+        attachment: &AttachmentDesc,
+        dims: [u32; 2],
+    ) -> Arc<AttachmentImage> {
+        if let Some((image, cached_dims, cached_format)) = self.images.get(&attachment.name.0) {
+            if *cached_dims == dims && *cached_format == attachment.format {
+                return image.clone();
+            }
+        }
+
+        let image = AttachmentImage::sampled(device.clone(), dims, attachment.format)
+            .expect("failed to allocate render-graph attachment image");
+        self.images
+            .insert(attachment.name.0.clone(), (image.clone(), dims, attachment.format));
+        image
+    }
+}
+
+impl RenderGraphBuilder {
+    /// Create an empty graph builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pass to the graph, returning its `PassId`. Dependencies between passes come from
+    /// their `reads`/`writes` resource names, not from `PassId`s.
+    pub fn add_pass(&mut self, pass: PassDesc) -> PassId {
+        let id = PassId(self.passes.len());
+        self.passes.push(pass);
+        id
+    }
+
+    /// Topologically sort the declared passes by their read/write dependencies and resolve the
+    /// transient image each pass writes to (via `cache`, so unchanged attachments are recycled
+    /// rather than reallocated), producing a `RenderGraph` ready to record commands against for
+    /// the given swapchain dimensions.
+    pub fn bake(
+        self,
+        device: &Arc<crate::vulkano::device::Device>,
+        cache: &mut RenderGraphCache,
+        swapchain_dims: [u32; 2],
+    ) -> RenderGraph {
+        let order = topological_order(&self.passes);
+
+        let mut resources: HashMap<String, Arc<AttachmentImage>> = HashMap::new();
+        for &idx in &order {
+            for attachment in &self.passes[idx].writes {
+                let dims = match attachment.size {
+                    AttachmentSize::SwapchainRelative => swapchain_dims,
+                    AttachmentSize::ScaledBy(scale) => [
+                        (swapchain_dims[0] as f32 * scale).max(1.0) as u32,
+                        (swapchain_dims[1] as f32 * scale).max(1.0) as u32,
+                    ],
+                };
+                let image = cache.get_or_allocate(device, attachment, dims);
+                resources.insert(attachment.name.0.clone(), image);
+            }
+        }
+
+        RenderGraph {
+            order,
+            passes: self.passes,
+            resources,
+        }
+    }
+}
+
+/// A baked, ready-to-record render graph for the current swapchain image.
+pub struct RenderGraph {
+    order: Vec<usize>,
+    passes: Vec<PassDesc>,
+    resources: HashMap<String, Arc<AttachmentImage>>,
+}
+
+impl RenderGraph {
+    /// The pass names in the order they will be recorded.
+    pub fn order(&self) -> impl Iterator<Item = &str> {
+        self.order.iter().map(move |&idx| self.passes[idx].name.as_str())
+    }
+
+    /// Retrieve the resolved image for a named output attachment (the `AttachmentDesc::name` a
+    /// pass declared via `PassDesc::writes`), for use as a sampled input to a later pass or as
+    /// the source for the final present.
+    pub fn output(&self, name: &str) -> Option<&Arc<AttachmentImage>> {
+        self.resources.get(name)
+    }
+
+    /// Record every pass's commands, in dependency order, onto `commands`. Before each pass, its
+    /// sampled inputs are transitioned to `ShaderReadOnlyOptimal` and every declared output
+    /// attachment (color or depth/stencil) to its matching attachment-optimal layout, mirroring
+    /// the transitions `view` would otherwise have to insert by hand for a hand-rolled multi-pass
+    /// setup. All of a pass's `writes` attachments are bound, in declaration order, to its
+    /// framebuffer — not just the first — so a pass with e.g. `.writes(color).writes(depth)`
+    /// gets a proper two-attachment framebuffer matching its `render_pass`. A pass declaring more
+    /// than two `writes` panics (see `framebuffer_for_outputs`).
+    pub fn record_commands(&self, mut commands: AutoCommandBufferBuilder) -> AutoCommandBufferBuilder {
+        for &idx in &self.order {
+            let pass = &self.passes[idx];
+
+            let mut inputs = HashMap::new();
+            for read in &pass.reads {
+                if let Some(image) = self.resources.get(&read.0) {
+                    commands = transition_layout(
+                        commands,
+                        image,
+                        ImageLayout::ColorAttachmentOptimal,
+                        ImageLayout::ShaderReadOnlyOptimal,
+                    );
+                    inputs.insert(read.0.clone(), image.clone());
+                }
+            }
+
+            assert!(
+                !pass.writes.is_empty(),
+                "pass {:?} must declare at least one output attachment",
+                pass.name
+            );
+            let mut output_images = Vec::with_capacity(pass.writes.len());
+            for attachment in &pass.writes {
+                let output_image = self
+                    .resources
+                    .get(&attachment.name.0)
+                    .expect("pass output was allocated during bake");
+                let new_layout = if is_depth_format(attachment.format) {
+                    ImageLayout::DepthStencilAttachmentOptimal
+                } else {
+                    ImageLayout::ColorAttachmentOptimal
+                };
+                commands = transition_layout(commands, output_image, ImageLayout::Undefined, new_layout);
+                output_images.push(output_image.clone());
+            }
+
+            let framebuffer = framebuffer_for_outputs(pass.render_pass.clone(), output_images);
+            let context = PassContext {
+                inputs,
+                render_pass: pass.render_pass.clone(),
+                framebuffer,
+            };
+
+            commands = (pass.record)(commands, &context);
+        }
+
+        commands
+    }
+}
+
+/// Whether `format` is a depth or depth/stencil format, so [`RenderGraph::record_commands`] can
+/// transition a pass's depth output to `DepthStencilAttachmentOptimal` instead of
+/// `ColorAttachmentOptimal`.
+fn is_depth_format(format: Format) -> bool {
+    match format {
+        Format::D16Unorm
+        | Format::D16Unorm_S8Uint
+        | Format::D24Unorm_S8Uint
+        | Format::D32Sfloat
+        | Format::D32Sfloat_S8Uint => true,
+        _ => false,
+    }
+}
+
+/// Insert a pipeline barrier transitioning `image` from `old_layout` to `new_layout`. Reads are
+/// expected to come from a prior color-attachment write and feed a fragment shader sampler;
+/// writes are expected to start from an undefined layout and become a color or depth/stencil
+/// attachment.
+fn transition_layout(
+    commands: AutoCommandBufferBuilder,
+    image: &Arc<AttachmentImage>,
+    old_layout: ImageLayout,
+    new_layout: ImageLayout,
+) -> AutoCommandBufferBuilder {
+    let (source_stage, source_access, dest_stage, dest_access) = match new_layout {
+        ImageLayout::ShaderReadOnlyOptimal => (
+            PipelineStages {
+                color_attachment_output: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                color_attachment_write: true,
+                ..AccessFlagBits::none()
+            },
+            PipelineStages {
+                fragment_shader: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                shader_read: true,
+                ..AccessFlagBits::none()
+            },
+        ),
+        ImageLayout::DepthStencilAttachmentOptimal => (
+            PipelineStages {
+                top_of_pipe: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits::none(),
+            PipelineStages {
+                early_fragment_tests: true,
+                late_fragment_tests: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                depth_stencil_attachment_write: true,
+                ..AccessFlagBits::none()
+            },
+        ),
+        _ => (
+            PipelineStages {
+                top_of_pipe: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits::none(),
+            PipelineStages {
+                color_attachment_output: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                color_attachment_write: true,
+                ..AccessFlagBits::none()
+            },
+        ),
+    };
+
+    unsafe {
+        commands
+            .image_memory_barrier(
+                image.clone(),
+                0..1,
+                0..1,
+                source_stage,
+                source_access,
+                dest_stage,
+                dest_access,
+                false,
+                None,
+                old_layout,
+                new_layout,
+            )
+            .expect("failed to insert render-graph image layout transition")
+    }
+}
+
+/// Depth-first topological sort over the declared read/write edges: a pass that reads a resource
+/// is ordered after the pass that writes it (the last-declared writer, if several write the same
+/// name). Cycles are not reported; a pass already visited is not revisited.
+fn topological_order(passes: &[PassDesc]) -> Vec<usize> {
+    let writer_of: HashMap<&str, usize> = passes
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, pass)| pass.writes.iter().map(move |a| (a.name.0.as_str(), idx)))
+        .collect();
+
+    let mut visited = vec![false; passes.len()];
+    let mut order = Vec::with_capacity(passes.len());
+
+    fn visit(
+        idx: usize,
+        passes: &[PassDesc],
+        writer_of: &HashMap<&str, usize>,
+        visited: &mut Vec<bool>,
+        order: &mut Vec<usize>,
+    ) {
+        if visited[idx] {
+            return;
+        }
+        visited[idx] = true;
+        for read in &passes[idx].reads {
+            if let Some(&dep) = writer_of.get(read.0.as_str()) {
+                visit(dep, passes, writer_of, visited, order);
+            }
+        }
+        order.push(idx);
+    }
+
+    for idx in 0..passes.len() {
+        visit(idx, passes, &writer_of, &mut visited, &mut order);
+    }
+
+    order
+}
+
+/// Build a `Framebuffer` for a node's resolved output images, attaching each in the order its
+/// `PassDesc::writes` calls declared them, mirroring the pattern the `vulkan` example uses for
+/// its swapchain framebuffer but generalised to a pass's attachment set.
+///
+/// `Framebuffer`'s builder records each attachment's type in its own generic signature, so it
+/// can't be built up from a runtime-length loop; we support the one- and two-attachment cases
+/// (a single color output, or color + depth/stencil) explicitly and refuse anything wider rather
+/// than silently dropping extra declared outputs.
+fn framebuffer_for_outputs(
+    render_pass: Arc<RenderPassAbstract + Send + Sync>,
+    images: Vec<Arc<AttachmentImage>>,
+) -> Arc<FramebufferAbstract + Send + Sync> {
+    match images.len() {
+        1 => Arc::new(
+            Framebuffer::start(render_pass)
+                .add(images[0].clone())
+                .expect("failed to attach render-graph output image")
+                .build()
+                .expect("failed to build render-graph framebuffer"),
+        ),
+        2 => Arc::new(
+            Framebuffer::start(render_pass)
+                .add(images[0].clone())
+                .expect("failed to attach render-graph color output image")
+                .add(images[1].clone())
+                .expect("failed to attach render-graph depth output image")
+                .build()
+                .expect("failed to build render-graph framebuffer"),
+        ),
+        n => panic!(
+            "render-graph passes with {} output attachments aren't supported yet (only 1 color, \
+             or 1 color + 1 depth/stencil)",
+            n
+        ),
+    }
+}
+
+/// Lets a `Frame` retrieve a baked render graph's named pass outputs, e.g. to sample the final
+/// pass's result when recording the present/composite step.
+pub trait FrameGraphExt {
+    /// The resolved output image for `name`, if a pass in `graph` declared an attachment by that
+    /// name via `PassDesc::writes`.
+    fn graph_output<'a>(&self, graph: &'a RenderGraph, name: &str) -> Option<&'a Arc<AttachmentImage>>;
+}
+
+impl FrameGraphExt for crate::window::Frame {
+    fn graph_output<'a>(&self, graph: &'a RenderGraph, name: &str) -> Option<&'a Arc<AttachmentImage>> {
+        graph.output(name)
+    }
+}